@@ -32,6 +32,45 @@ pub struct NewSlotInfo {
 pub type NewSlotNotifier = std::sync::Arc<Box<dyn (Fn() -> std::sync::mpsc::Receiver<
 	(NewSlotInfo, std::sync::mpsc::SyncSender<Option<Solution>>)
 >) + Send + Sync>>;
+/// Information about a slot that just became rooted/finalized
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RootInfo {
+	/// Slot number that was rooted
+	pub slot_number: Slot,
+	/// Unix time (in milliseconds) at which the slot was rooted
+	pub timestamp: u64,
+}
+/// A function that can be called whenever it is necessary to create a subscription for rooted slots
+pub type RootNotifier = std::sync::Arc<Box<dyn (Fn() -> std::sync::mpsc::Receiver<RootInfo>) + Send + Sync>>;
+/// A single point in the lifecycle of a slot, as observed by the node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SlotUpdate {
+	/// The first block for this slot was produced
+	FirstBlockProduced {
+		/// Slot number
+		slot: Slot,
+		/// Unix time (in milliseconds) at which the block was produced
+		timestamp: u64,
+	},
+	/// The block for this slot was sealed
+	Frozen {
+		/// Slot number
+		slot: Slot,
+		/// Unix time (in milliseconds) at which the block was sealed
+		timestamp: u64,
+	},
+	/// The slot became finalized/rooted
+	Root {
+		/// Slot number
+		slot: Slot,
+		/// Unix time (in milliseconds) at which the slot was rooted
+		timestamp: u64,
+	},
+}
+/// A function that can be called whenever it is necessary to create a subscription for slot
+/// lifecycle updates
+pub type SlotUpdateNotifier = std::sync::Arc<Box<dyn (Fn() -> std::sync::mpsc::Receiver<SlotUpdate>) + Send + Sync>>;
 #[derive(Clone)]
 pub struct Solution {
 	pub public_key: FarmerId,
@@ -42,37 +81,117 @@ pub struct Solution {
 }
 
 //use sc_consensus_poc::{NewSlotNotifier, NewSlotInfo};
-use futures::{FutureExt as _, TryFutureExt as _, SinkExt, TryStreamExt, StreamExt};
-use jsonrpc_core::{
-	Error as RpcError,
-	futures::future as rpc_future,
-	Result as RpcResult,
-	futures::{
-		Future,
-		Sink,
-		Stream,
-		future::Future as Future01,
-		future::Executor as Executor01,
-	},
+use futures::{channel::mpsc, SinkExt, StreamExt};
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::SubscriptionResult,
+	PendingSubscriptionSink, SubscriptionMessage, SubscriptionSink,
 };
-use jsonrpc_derive::rpc;
-use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId, manager::SubscriptionManager};
 use sp_consensus_poc::FarmerId;
 use serde::{Deserialize, Serialize};
 use sp_core::crypto::Public;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+};
 use log::{debug, warn};
-use std::sync::mpsc;
 use parking_lot::Mutex;
-use futures::channel::mpsc::UnboundedSender;
-use futures::future;
-use futures::future::Either;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
 const SOLUTION_TIMEOUT: Duration = Duration::from_secs(5);
+/// Default depth of the per-subscriber bounded notification queue, see [`NotificationQueue`].
+pub const DEFAULT_NOTIFICATION_QUEUE_DEPTH: usize = 100;
 
 type Slot = u64;
-type FutureResult<T> = Box<dyn rpc_future::Future<Item = T, Error = RpcError> + Send>;
+
+/// Fans notifications out to subscribers without letting a slow one stall everyone else.
+///
+/// Each subscriber gets its own bounded queue of depth `queue_depth`; a dedicated task drains
+/// `notify`d items and forwards them via `try_send`, dropping (and closing) any subscriber
+/// whose queue is full instead of blocking on it. This keeps producers, such as the new-slot
+/// solution-collection loop, decoupled from how fast individual WebSocket clients consume
+/// notifications.
+struct NotificationQueue<T> {
+	queue_depth: usize,
+	incoming: mpsc::UnboundedSender<T>,
+	subscribers: Arc<Mutex<Vec<mpsc::Sender<T>>>>,
+	dropped_notifications: Arc<AtomicU64>,
+}
+
+impl<T: Clone + Serialize + Send + Sync + 'static> NotificationQueue<T> {
+	/// Creates a new notification queue and spawns its draining task.
+	fn new(queue_depth: usize) -> Self {
+		let (incoming, mut incoming_receiver) = mpsc::unbounded::<T>();
+		let subscribers: Arc<Mutex<Vec<mpsc::Sender<T>>>> = Arc::default();
+		let dropped_notifications: Arc<AtomicU64> = Arc::default();
+		tokio::spawn({
+			let subscribers = Arc::clone(&subscribers);
+			let dropped_notifications = Arc::clone(&dropped_notifications);
+
+			async move {
+				while let Some(notification) = incoming_receiver.next().await {
+					let mut subscribers = subscribers.lock();
+					subscribers.retain_mut(|subscriber| {
+						match subscriber.try_send(notification.clone()) {
+							Ok(()) => true,
+							Err(error) => {
+								if error.is_full() {
+									dropped_notifications.fetch_add(1, Ordering::Relaxed);
+								}
+								false
+							}
+						}
+					});
+				}
+			}
+		});
+
+		Self {
+			queue_depth,
+			incoming,
+			subscribers,
+			dropped_notifications,
+		}
+	}
+
+	/// Registers `sink` as a new subscriber, spawning a task that forwards notifications to it
+	/// until its bounded queue overflows or the subscription is closed.
+	fn subscribe(&self, mut sink: SubscriptionSink) {
+		let (tx, mut rx) = mpsc::channel(self.queue_depth);
+		self.subscribers.lock().push(tx);
+		tokio::spawn(async move {
+			while let Some(notification) = rx.next().await {
+				let message = match SubscriptionMessage::from_json(&notification) {
+					Ok(message) => message,
+					Err(error) => {
+						warn!("Failed to serialize notification: {:?}", error);
+						continue;
+					}
+				};
+				if sink.send(message).await.is_err() {
+					break;
+				}
+			}
+		});
+	}
+
+	/// Number of currently registered subscribers.
+	fn subscriber_count(&self) -> usize {
+		self.subscribers.lock().len()
+	}
+
+	/// Enqueues a notification for asynchronous fan-out to subscribers; never blocks the caller.
+	fn notify(&self, notification: T) {
+		let _ = self.incoming.unbounded_send(notification);
+	}
+
+	/// Number of notifications dropped so far because a subscriber's queue was full.
+	fn dropped_notifications(&self) -> u64 {
+		self.dropped_notifications.load(Ordering::Relaxed)
+	}
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RpcSolution {
@@ -86,155 +205,443 @@ pub struct RpcSolution {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProposedProofOfSpaceResult {
 	slot_number: Slot,
+	/// Identifies the farmer responding, whether or not it found a solution. Required on every
+	/// response (not just `Some(solution)`) so a single farmer can't bypass the ack-once-per-slot
+	/// rule by repeatedly submitting `solution: None`.
+	public_key: [u8; 32],
 	solution: Option<RpcSolution>,
 }
 
+/// Treats `tag` and `challenge` as points on a 64-bit ring and returns the shorter distance
+/// between them, i.e. `min(|tag - challenge|, 2^64 - |tag - challenge|)`.
+fn ring_distance(tag: u64, challenge: u64) -> u64 {
+	let diff = tag.abs_diff(challenge);
+	diff.min(diff.wrapping_neg())
+}
+
+/// Records that `farmer_id` has responded for a slot, storing its solution if it submitted one.
+/// A farmer may only affect the outcome once per slot: a repeat response from the same
+/// `farmer_id` — with or without a solution — is silently ignored rather than overwriting the
+/// first one. Returns `true` if this was the farmer's first response for the slot, i.e. the
+/// caller should ack it.
+fn record_submission(
+	responded_farmers: &mut HashSet<FarmerId>,
+	solutions: &mut HashMap<FarmerId, RpcSolution>,
+	farmer_id: FarmerId,
+	solution: Option<RpcSolution>,
+) -> bool {
+	if !responded_farmers.insert(farmer_id) {
+		return false;
+	}
+	if let Some(solution) = solution {
+		solutions.insert(farmer_id, solution);
+	}
+	true
+}
+
+/// Picks the submitted solution whose `tag` is closest (by ring distance) to `challenge`,
+/// rejecting any solution outside of `solution_range`. Returns `None` if there is no submission
+/// within range.
+fn select_best_solution(
+	submissions: HashMap<FarmerId, RpcSolution>,
+	challenge: u64,
+	solution_range: u64,
+) -> Option<Solution> {
+	submissions
+		.into_iter()
+		.filter_map(|(farmer_id, solution)| {
+			let tag = u64::from_be_bytes(solution.tag);
+			let distance = ring_distance(tag, challenge);
+			if distance <= solution_range / 2 {
+				Some((distance, farmer_id, solution))
+			} else {
+				None
+			}
+		})
+		.min_by_key(|(distance, _, _)| *distance)
+		.map(|(_, farmer_id, solution)| Solution {
+			public_key: farmer_id,
+			nonce: solution.nonce,
+			encoding: solution.encoding,
+			signature: solution.signature,
+			tag: solution.tag,
+		})
+}
+
 /// Provides rpc methods for interacting with PoC.
-#[rpc]
+#[rpc(server, client)]
 pub trait PoCApi {
-	/// RPC metadata
-	type Metadata;
+	#[method(name = "poc_proposeProofOfSpace")]
+	async fn propose_proof_of_space(
+		&self,
+		proposed_proof_of_space_result: ProposedProofOfSpaceResult,
+	) -> RpcResult<()>;
 
-	#[rpc(name = "poc_proposeProofOfSpace")]
-	fn propose_proof_of_space(&self, proposed_proof_of_space_result: ProposedProofOfSpaceResult) -> FutureResult<()>;
+	/// Slot info subscription
+	#[subscription(
+		name = "poc_subscribeSlotInfo" => "poc_slot_info",
+		unsubscribe = "poc_unsubscribeSlotInfo",
+		item = NewSlotInfo,
+	)]
+	async fn subscribe_slot_info(&self) -> SubscriptionResult;
 
+	/// Root info subscription, fires whenever a slot becomes finalized/rooted
+	#[subscription(
+		name = "poc_subscribeRootInfo" => "poc_root_info",
+		unsubscribe = "poc_unsubscribeRootInfo",
+		item = RootInfo,
+	)]
+	async fn subscribe_root_info(&self) -> SubscriptionResult;
 
-	/// Slot info subscription
-	#[pubsub(subscription = "poc_slot_info", subscribe, name = "poc_subscribeSlotInfo")]
-	fn subscribe_slot_info(&self, metadata: Self::Metadata, subscriber: Subscriber<NewSlotInfo>);
+	/// Slot lifecycle update subscription, fires on each transition a slot goes through
+	#[subscription(
+		name = "poc_subscribeSlotUpdates" => "poc_slot_updates",
+		unsubscribe = "poc_unsubscribeSlotUpdates",
+		item = SlotUpdate,
+	)]
+	async fn subscribe_slot_updates(&self) -> SubscriptionResult;
+}
 
-	/// Unsubscribe from slot info subscription.
-	#[pubsub(subscription = "poc_slot_info", unsubscribe, name = "poc_unsubscribeSlotInfo")]
-	fn unsubscribe_slot_info(
-		&self,
-		metadata: Option<Self::Metadata>,
-		id: SubscriptionId,
-	) -> RpcResult<bool>;
+/// Per-slot state tracked while waiting for farmers to respond to a `NewSlotInfo` notification.
+/// Kept behind a single lock (see [`PoCRpcHandler::slot_submissions`]) so that checking whether
+/// the slot is still open, deduping by farmer, and recording a solution all happen atomically —
+/// a late response racing slot finalization can neither double-ack nor resurrect a removed entry.
+struct SlotSubmissions {
+	/// Acked once per distinct farmer that responds; see `record_submission`.
+	ack_sender: mpsc::Sender<()>,
+	responded_farmers: HashSet<FarmerId>,
+	solutions: HashMap<FarmerId, RpcSolution>,
 }
 
 /// Implements the PoCRpc trait for interacting with PoC.
 pub struct PoCRpcHandler {
-	manager: SubscriptionManager,
-	notification_senders: Arc<Mutex<Vec<UnboundedSender<NewSlotInfo>>>>,
-	solution_senders: Arc<Mutex<HashMap<Slot, futures::channel::mpsc::Sender<Option<RpcSolution>>>>>,
+	notification_queue: Arc<NotificationQueue<NewSlotInfo>>,
+	root_notification_queue: Arc<NotificationQueue<RootInfo>>,
+	slot_update_queue: Arc<NotificationQueue<SlotUpdate>>,
+	slot_submissions: Arc<Mutex<HashMap<Slot, SlotSubmissions>>>,
 }
 
 // TODO: Add more detailed documentation
 impl PoCRpcHandler {
 	/// Creates a new instance of the PoCRpc handler.
-	pub fn new<E>(
-		executor: E,
+	///
+	/// `notification_queue_depth` bounds how many unconsumed notifications each subscriber may
+	/// accumulate before it is treated as a slow consumer and dropped; see
+	/// [`DEFAULT_NOTIFICATION_QUEUE_DEPTH`] for a sensible default.
+	///
+	/// Must be called from within a Tokio runtime, since it spawns the tasks that bridge the
+	/// node's notifiers into the subscriptions below.
+	pub fn new(
 		new_slot_notifier: NewSlotNotifier,
-	) -> Self
-		where
-			E: Executor01<Box<dyn Future01<Item = (), Error = ()> + Send>> + Send + Sync + 'static,
-	{
-		let notification_senders: Arc<Mutex<Vec<UnboundedSender<NewSlotInfo>>>> = Arc::default();
-		let solution_senders: Arc<Mutex<HashMap<Slot, futures::channel::mpsc::Sender<Option<RpcSolution>>>>> = Arc::default();
+		root_notifier: RootNotifier,
+		slot_update_notifier: SlotUpdateNotifier,
+		notification_queue_depth: usize,
+	) -> Self {
+		let notification_queue = Arc::new(NotificationQueue::new(notification_queue_depth));
+		let root_notification_queue = Arc::new(NotificationQueue::new(notification_queue_depth));
+		let slot_update_queue = Arc::new(NotificationQueue::new(notification_queue_depth));
+		let slot_submissions: Arc<Mutex<HashMap<Slot, SlotSubmissions>>> = Arc::default();
+
+		// NOTE: this is a partial port, not a full one. `{Root,SlotUpdate,NewSlot}Notifier`'s
+		// signatures (defined at the top of this file) still hand back a blocking
+		// `std::sync::mpsc::Receiver`, so each one is still drained by a dedicated OS thread
+		// doing a synchronous `.recv()` rather than an async channel. What *is* async now is
+		// everything downstream of that bridge: fan-out to subscribers, solution collection,
+		// and the `SOLUTION_TIMEOUT` wait. Fully removing these threads would require changing
+		// the notifier types themselves, which is out of scope here.
+		std::thread::Builder::new()
+			.name("poc_rpc_root_handler".to_string())
+			.spawn({
+				let root_notification_queue = Arc::clone(&root_notification_queue);
+				let root_notifier: std::sync::mpsc::Receiver<RootInfo> = root_notifier();
+
+				move || {
+					while let Ok(root_info) = root_notifier.recv() {
+						root_notification_queue.notify(root_info);
+					}
+				}
+			})
+			.expect("Failed to spawn poc rpc root notifier handler");
+		std::thread::Builder::new()
+			.name("poc_rpc_slot_update_handler".to_string())
+			.spawn({
+				let slot_update_queue = Arc::clone(&slot_update_queue);
+				let slot_update_notifier: std::sync::mpsc::Receiver<SlotUpdate> = slot_update_notifier();
+
+				move || {
+					while let Ok(slot_update) = slot_update_notifier.recv() {
+						slot_update_queue.notify(slot_update);
+					}
+				}
+			})
+			.expect("Failed to spawn poc rpc slot update notifier handler");
+
+		let (new_slot_sender, mut new_slot_receiver) = mpsc::unbounded::<
+			(NewSlotInfo, std::sync::mpsc::SyncSender<Option<Solution>>)
+		>();
 		std::thread::Builder::new()
-			.name("poc_rpc_nsn_handler".to_string())
+			.name("poc_rpc_nsn_bridge".to_string())
 			.spawn({
-				let notification_senders = Arc::clone(&notification_senders);
-				let solution_senders = Arc::clone(&solution_senders);
 				let new_slot_notifier: std::sync::mpsc::Receiver<
-					(NewSlotInfo, mpsc::SyncSender<Option<Solution>>)
+					(NewSlotInfo, std::sync::mpsc::SyncSender<Option<Solution>>)
 				> = new_slot_notifier();
 
 				move || {
-					while let Ok((new_slot_info, sync_solution_sender)) = new_slot_notifier.recv() {
-						futures::executor::block_on(async {
-							let (solution_sender, mut solution_receiver) = futures::channel::mpsc::channel(0);
-							solution_senders.lock().insert(new_slot_info.slot_number, solution_sender);
-							let mut expected_solutions_count;
-							{
-								let mut notification_senders = notification_senders.lock();
-								expected_solutions_count = notification_senders.len();
-								if expected_solutions_count == 0 {
-									let _ = sync_solution_sender.send(None);
-									return;
-								}
-								for notification_sender in notification_senders.iter_mut() {
-									if notification_sender.send(new_slot_info.clone()).await.is_err() {
-										expected_solutions_count -= 1;
-									}
-								}
-							}
-
-							let timeout = futures_timer::Delay::new(SOLUTION_TIMEOUT).map(|_| None);
-							let solution = async move {
-								// TODO: This doesn't track what client sent a solution, allowing
-								//  some clients to send multiple
-								let mut potential_solutions_left = expected_solutions_count;
-								while let Some(solution) = solution_receiver.next().await {
-									if let Some(solution) = solution {
-										return Some(Solution {
-											public_key: FarmerId::from_slice(&solution.public_key),
-											nonce: solution.nonce,
-											encoding: solution.encoding,
-											signature: solution.signature,
-											tag: solution.tag,
-										});
-									}
-									potential_solutions_left -= 1;
-									if potential_solutions_left == 0 {
-										break;
-									}
-								}
+					while let Ok(new_slot) = new_slot_notifier.recv() {
+						if new_slot_sender.unbounded_send(new_slot).is_err() {
+							break;
+						}
+					}
+				}
+			})
+			.expect("Failed to spawn poc rpc new slot notifier bridge");
 
-								return None;
-							};
+		tokio::spawn({
+			let notification_queue = Arc::clone(&notification_queue);
+			let slot_submissions = Arc::clone(&slot_submissions);
 
-							let solution = match future::select(timeout, Box::pin(solution)).await {
-								Either::Left((value1, _)) => value1,
-								Either::Right((value2, _)) => value2,
-							};
+			async move {
+				while let Some((new_slot_info, sync_solution_sender)) = new_slot_receiver.next().await {
+					let (ack_sender, mut solution_receiver) = mpsc::channel(0);
+					slot_submissions.lock().insert(
+						new_slot_info.slot_number,
+						SlotSubmissions {
+							ack_sender,
+							responded_farmers: HashSet::new(),
+							solutions: HashMap::new(),
+						},
+					);
+					let expected_solutions_count = notification_queue.subscriber_count();
+					if expected_solutions_count == 0 {
+						slot_submissions.lock().remove(&new_slot_info.slot_number);
+						let _ = sync_solution_sender.send(None);
+						continue;
+					}
+					// Enqueueing is non-blocking; fan-out to subscribers happens on the queue's
+					// own task so a slow WebSocket client can never stall solution collection
+					// for everyone else.
+					notification_queue.notify(new_slot_info.clone());
 
-							if let Err(error) = sync_solution_sender.send(solution) {
-								debug!("Failed to send solution: {}", error);
+					// Each ack on this channel means one *distinct* farmer has responded for this
+					// slot (see `record_submission`, which every response funnels through,
+					// whether or not it carries a solution), so this reaches zero exactly when
+					// every notified farmer has responded — never early because of a
+					// retried/duplicated response. Cancellation-safe: if the timeout fires
+					// first, `wait_for_submissions` is simply dropped mid-await without leaving
+					// anything dangling.
+					let mut potential_solutions_left = expected_solutions_count;
+					let wait_for_submissions = async {
+						while potential_solutions_left > 0 {
+							if solution_receiver.next().await.is_none() {
+								break;
 							}
+							potential_solutions_left -= 1;
+						}
+					};
+					let _ = tokio::time::timeout(SOLUTION_TIMEOUT, wait_for_submissions).await;
+
+					let challenge = u64::from_be_bytes(new_slot_info.challenge);
+					let solution_range = new_slot_info.solution_range;
+					let solutions = slot_submissions
+						.lock()
+						.remove(&new_slot_info.slot_number)
+						.map(|state| state.solutions)
+						.unwrap_or_default();
+					let solution = select_best_solution(solutions, challenge, solution_range);
 
-							solution_senders.lock().remove(&new_slot_info.slot_number);
-						});
+					if let Err(error) = sync_solution_sender.send(solution) {
+						debug!("Failed to send solution: {}", error);
 					}
 				}
-			})
-			.expect("Failed to spawn poc rpc new slot notifier handler");
-		let manager = SubscriptionManager::new(Arc::new(executor));
+			}
+		});
+
 		Self {
-			manager,
-			notification_senders,
-			solution_senders,
+			notification_queue,
+			root_notification_queue,
+			slot_update_queue,
+			slot_submissions,
 		}
 	}
+
+	/// Number of `poc_subscribeSlotInfo` notifications dropped so far because a subscriber's
+	/// queue was full (i.e. it was not consuming fast enough).
+	pub fn dropped_slot_info_notifications(&self) -> u64 {
+		self.notification_queue.dropped_notifications()
+	}
+
+	/// Number of `poc_subscribeRootInfo` notifications dropped so far because a subscriber's
+	/// queue was full (i.e. it was not consuming fast enough).
+	pub fn dropped_root_info_notifications(&self) -> u64 {
+		self.root_notification_queue.dropped_notifications()
+	}
+
+	/// Number of `poc_subscribeSlotUpdates` notifications dropped so far because a subscriber's
+	/// queue was full (i.e. it was not consuming fast enough).
+	pub fn dropped_slot_update_notifications(&self) -> u64 {
+		self.slot_update_queue.dropped_notifications()
+	}
 }
 
-impl PoCApi for PoCRpcHandler {
-	type Metadata = sc_rpc_api::Metadata;
+#[async_trait]
+impl PoCApiServer for PoCRpcHandler {
+	async fn propose_proof_of_space(
+		&self,
+		proposed_proof_of_space_result: ProposedProofOfSpaceResult,
+	) -> RpcResult<()> {
+		let slot_number = proposed_proof_of_space_result.slot_number;
+		let farmer_id = FarmerId::from_slice(&proposed_proof_of_space_result.public_key);
 
-	fn propose_proof_of_space(&self, proposed_proof_of_space_result: ProposedProofOfSpaceResult) -> FutureResult<()> {
-		let sender = self.solution_senders.lock().get(&proposed_proof_of_space_result.slot_number).cloned();
-		let future = async move {
-			if let Some(mut sender) = sender {
-				let _ = sender.send(proposed_proof_of_space_result.solution).await;
+		// Look up the slot and dedup-and-record the response under a single lock guard: holding
+		// the lock across both the "is this slot still open" check and the insert is what
+		// prevents a response racing slot finalization from recreating a zombie entry for a slot
+		// that has already been removed below.
+		let mut ack_sender = {
+			let mut slot_submissions = self.slot_submissions.lock();
+			let Some(state) = slot_submissions.get_mut(&slot_number) else {
+				return Ok(());
+			};
+			let should_ack = record_submission(
+				&mut state.responded_farmers,
+				&mut state.solutions,
+				farmer_id,
+				proposed_proof_of_space_result.solution,
+			);
+			if !should_ack {
+				return Ok(());
 			}
+			state.ack_sender.clone()
+		};
+		let _ = ack_sender.send(()).await;
 
-			Ok(())
-		}.boxed();
-		Box::new(future.compat())
+		Ok(())
 	}
 
-	fn subscribe_slot_info(&self, _metadata: Self::Metadata, subscriber: Subscriber<NewSlotInfo>) {
-		self.manager.add(subscriber, |sink| {
-			let (tx, rx) = futures::channel::mpsc::unbounded();
-			self.notification_senders.lock().push(tx);
-			sink
-				.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
-				.send_all(rx.map(Ok::<_, ()>).compat().map(|res| Ok(res)))
-				.map(|_| ())
-		});
+	async fn subscribe_slot_info(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+		let sink = pending.accept().await?;
+		self.notification_queue.subscribe(sink);
+		Ok(())
+	}
+
+	async fn subscribe_root_info(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+		let sink = pending.accept().await?;
+		self.root_notification_queue.subscribe(sink);
+		Ok(())
+	}
+
+	async fn subscribe_slot_updates(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+		let sink = pending.accept().await?;
+		self.slot_update_queue.subscribe(sink);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn solution(public_key: [u8; 32], tag: [u8; 8]) -> RpcSolution {
+		RpcSolution {
+			public_key,
+			nonce: 0,
+			encoding: Vec::new(),
+			signature: Vec::new(),
+			tag,
+		}
+	}
+
+	fn record(
+		responded_farmers: &mut HashSet<FarmerId>,
+		solutions: &mut HashMap<FarmerId, RpcSolution>,
+		public_key: [u8; 32],
+		solution: Option<RpcSolution>,
+	) -> bool {
+		record_submission(responded_farmers, solutions, FarmerId::from_slice(&public_key), solution)
+	}
+
+	#[test]
+	fn ring_distance_is_symmetric_and_wraps_around() {
+		assert_eq!(ring_distance(10, 10), 0);
+		assert_eq!(ring_distance(10, 15), 5);
+		assert_eq!(ring_distance(15, 10), 5);
+		// 0 and u64::MAX are adjacent on the ring, not far apart
+		assert_eq!(ring_distance(0, u64::MAX), 1);
+		assert_eq!(ring_distance(u64::MAX, 0), 1);
+	}
+
+	#[test]
+	fn record_submission_keeps_first_for_same_farmer() {
+		let mut responded_farmers = HashSet::new();
+		let mut solutions = HashMap::new();
+		let public_key = [1; 32];
+
+		assert!(record(&mut responded_farmers, &mut solutions, public_key, Some(solution(public_key, [0; 8]))));
+		assert!(!record(&mut responded_farmers, &mut solutions, public_key, Some(solution(public_key, [0xff; 8]))));
+
+		let farmer_id = FarmerId::from_slice(&public_key);
+		assert_eq!(solutions[&farmer_id].tag, [0; 8]);
+	}
+
+	#[test]
+	fn record_submission_ignores_repeat_none_after_none() {
+		// A farmer that reports "no solution" twice for the same slot must only be able to
+		// affect the ack count once, just like a farmer resubmitting a solution.
+		let mut responded_farmers = HashSet::new();
+		let mut solutions = HashMap::new();
+		let public_key = [1; 32];
+
+		assert!(record(&mut responded_farmers, &mut solutions, public_key, None));
+		assert!(!record(&mut responded_farmers, &mut solutions, public_key, None));
+		assert!(!record(&mut responded_farmers, &mut solutions, public_key, Some(solution(public_key, [0; 8]))));
+		assert!(solutions.is_empty());
+	}
+
+	#[test]
+	fn record_submission_tracks_distinct_farmers_separately() {
+		let mut responded_farmers = HashSet::new();
+		let mut solutions = HashMap::new();
+
+		assert!(record(&mut responded_farmers, &mut solutions, [1; 32], Some(solution([1; 32], [0; 8]))));
+		assert!(record(&mut responded_farmers, &mut solutions, [2; 32], Some(solution([2; 32], [0; 8]))));
+
+		assert_eq!(solutions.len(), 2);
+	}
+
+	#[test]
+	fn select_best_solution_picks_closest_to_challenge() {
+		let mut responded_farmers = HashSet::new();
+		let mut submissions = HashMap::new();
+		record(&mut responded_farmers, &mut submissions, [1; 32], Some(solution([1; 32], 9u64.to_be_bytes())));
+		record(&mut responded_farmers, &mut submissions, [2; 32], Some(solution([2; 32], 12u64.to_be_bytes())));
+		record(&mut responded_farmers, &mut submissions, [3; 32], Some(solution([3; 32], 100u64.to_be_bytes())));
+
+		let best = select_best_solution(submissions, 11, 1_000).expect("a solution within range");
+		assert_eq!(best.tag, 12u64.to_be_bytes());
+	}
+
+	#[test]
+	fn select_best_solution_rejects_out_of_range() {
+		let mut responded_farmers = HashSet::new();
+		let mut submissions = HashMap::new();
+		record(&mut responded_farmers, &mut submissions, [1; 32], Some(solution([1; 32], 1_000u64.to_be_bytes())));
+
+		assert!(select_best_solution(submissions, 0, 10).is_none());
+	}
+
+	#[test]
+	fn select_best_solution_breaks_ties_with_a_valid_candidate() {
+		let mut responded_farmers = HashSet::new();
+		let mut submissions = HashMap::new();
+		record(&mut responded_farmers, &mut submissions, [1; 32], Some(solution([1; 32], 5u64.to_be_bytes())));
+		record(&mut responded_farmers, &mut submissions, [2; 32], Some(solution([2; 32], 15u64.to_be_bytes())));
+
+		// Both candidates are exactly 5 away from the challenge; either is an acceptable pick.
+		let best = select_best_solution(submissions, 10, 1_000).expect("a solution within range");
+		assert!(best.tag == 5u64.to_be_bytes() || best.tag == 15u64.to_be_bytes());
 	}
 
-	fn unsubscribe_slot_info(&self, _metadata: Option<Self::Metadata>, id: SubscriptionId) -> RpcResult<bool> {
-		Ok(self.manager.cancel(id))
+	#[test]
+	fn select_best_solution_with_no_submissions_returns_none() {
+		assert!(select_best_solution(HashMap::new(), 0, 1_000).is_none());
 	}
 }